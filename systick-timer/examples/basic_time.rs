@@ -3,10 +3,10 @@
 #![no_main]
 
 use cortex_m_semihosting::hprintln;
-use systick_timer::Timer;
+use systick_timer::{CortexMSysTick, Timer};
 
 // Set up for micro-second resolution, reload every 100 microseconds, 8 MHz clock
-static INSTANCE: Timer = Timer::new(1_000_000, 799, 8_000_000);
+static INSTANCE: Timer<CortexMSysTick> = Timer::new(1_000_000, 799, 8_000_000, CortexMSysTick);
 
 #[cortex_m_rt::entry]
 fn main() -> ! {
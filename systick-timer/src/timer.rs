@@ -2,30 +2,62 @@
 
 use core::sync::atomic::{AtomicU32, Ordering};
 
-#[cfg(test)]
-use core::sync::atomic::AtomicBool;
+mod seqlock;
+use seqlock::{ScalingState, SeqLock};
+
+mod source;
+pub use source::SysTickSource;
+#[cfg(feature = "cortex-m")]
+pub use source::CortexMSysTick;
+pub use source::MockSysTick;
+
+#[cfg(feature = "embassy-time-driver")]
+mod embassy;
+#[cfg(feature = "embassy-time-driver")]
+pub use embassy::ALARM_COUNT;
+
+#[cfg(feature = "rtic-monotonic")]
+mod rtic;
+#[cfg(feature = "rtic-monotonic")]
+pub use rtic::SystickMonotonic;
+
+#[cfg(feature = "embedded-hal-delay")]
+mod delay;
+
+#[cfg(feature = "async-sleep")]
+mod wheel;
+#[cfg(feature = "async-sleep")]
+pub use wheel::{Sleep, TimedOut, Timeout};
+
+#[cfg(feature = "periodic-events")]
+mod periodic;
+#[cfg(feature = "periodic-events")]
+pub use periodic::{PeriodicHandle, PERIODIC_COUNT};
 
 /// A 64-bit timer based on SysTick.
 ///
 /// Stores wraparounds in 2 32-bit atomics. Scales the systick counts
 /// to arbitrary frequency.
-pub struct Timer {
+///
+/// Generic over the down-counter it reads, `S: `[`SysTickSource`]: plug in
+/// [`CortexMSysTick`] on real hardware, or [`MockSysTick`] to drive the
+/// scaling/monotonicity logic without one (as the test suite does).
+pub struct Timer<S: SysTickSource> {
     inner_wraps: AtomicU32, // Counts SysTick interrupts (lower 32 bits)
     outer_wraps: AtomicU32, // Counts overflows of inner_wraps (upper 32 bits)
     reload_value: u32,      // SysTick reload value (max 2^24 - 1)
-    multiplier: u64,        // Precomputed for scaling cycles to ticks
-    shift: u32,             // Precomputed for scaling efficiency
-    #[cfg(test)]
-    current_systick: AtomicU32,
-    #[cfg(test)]
-    systick_has_wrapped: AtomicBool, // emulated COUNTFLAG (read-to-clear)
-    #[cfg(test)]
-    after_v1_hook: Option<fn(&Timer)>, // injected nested call site
-    #[cfg(test)]
-    pendst_is_pending: AtomicBool, // emulated SCB->ICSR PENDSTSET bit
+    tick_hz: u64,           // Configured output tick frequency
+    scaling: SeqLock,       // multiplier/shift plus the `reconfigure` continuity anchor
+    source: S,              // the down-counter this timer reads
+    #[cfg(feature = "embassy-time-driver")]
+    alarms: [embassy::AlarmSlot; embassy::ALARM_COUNT],
+    #[cfg(feature = "async-sleep")]
+    wheel: wheel::WheelStorage,
+    #[cfg(feature = "periodic-events")]
+    periodic: [periodic::PeriodicSlot; periodic::PERIODIC_COUNT],
 }
 
-impl Timer {
+impl<S: SysTickSource> Timer<S> {
     /// SysTick handler.
     ///
     /// Call this from the SysTick interrupt handler.
@@ -44,6 +76,15 @@ impl Timer {
             let outer = self.outer_wraps.load(Ordering::Relaxed).wrapping_add(1);
             self.outer_wraps.store(outer, Ordering::SeqCst);
         }
+
+        #[cfg(feature = "embassy-time-driver")]
+        self.poll_alarms();
+
+        #[cfg(feature = "async-sleep")]
+        self.poll_timers();
+
+        #[cfg(feature = "periodic-events")]
+        self.poll_periodic();
     }
 
     /// Robust `now()` (VAL-jump tie-breaker, no COUNTFLAG dependency).
@@ -61,6 +102,19 @@ impl Timer {
     /// becomes insufficient and monotonic violations occur. The ISR starvation detection logic
     /// in `diagnose_timing_violation()` identifies these as catastrophic "N+1 missed wraps".
     pub fn now(&self) -> u64 {
+        self.cycles_to_ticks(self.now_cycles())
+    }
+
+    /// Race-safe read of the raw, unscaled SysTick cycle count.
+    ///
+    /// This is the same coherent `(wraps, final_val)` snapshot `now()`
+    /// scales into ticks via [`Timer::cycles_to_ticks`]; exposed directly
+    /// for callers that want the raw cycle count instead — profiling,
+    /// custom fixed-point conversions, or feeding another time base —
+    /// without `now()`'s lossy multiply/shift. Also used by
+    /// [`Timer::adjust_ppb`] to anchor a new scaling factor to the exact
+    /// cycle count `now()` itself would have used.
+    pub fn now_cycles(&self) -> u64 {
         let reload = self.reload_value as u64;
 
         loop {
@@ -121,64 +175,157 @@ impl Timer {
                 .saturating_mul(reload + 1)
                 .saturating_add(reload - final_val);
 
-            // Scale to ticks.
-            let (result, overflow) = total_cycles.overflowing_mul(self.multiplier);
-            if !overflow {
-                return result >> self.shift;
-            } else {
-                let wide = (total_cycles as u128) * (self.multiplier as u128);
-                return (wide >> self.shift) as u64;
-            }
+            return total_cycles;
         }
     }
 
+    /// Scales a raw cycle count (as returned by [`Timer::now_cycles`]) into
+    /// ticks, honoring the continuity anchor set by [`Timer::reconfigure`]
+    /// (a no-op anchor for timers that have never been reconfigured).
+    ///
+    /// `now()` is defined as `cycles_to_ticks(now_cycles())`; round-tripping
+    /// a cycle count read separately through this function reproduces
+    /// exactly what `now()` would have returned at that instant.
+    pub fn cycles_to_ticks(&self, total_cycles: u64) -> u64 {
+        let state = self.scaling.read();
+        let delta_cycles = total_cycles.saturating_sub(state.base_cycles);
+
+        let (result, overflow) = delta_cycles.overflowing_mul(state.multiplier);
+        let scaled = if !overflow {
+            result >> state.shift
+        } else {
+            let wide = (delta_cycles as u128) * (state.multiplier as u128);
+            (wide >> state.shift) as u64
+        };
+
+        state.base_ticks.wrapping_add(scaled)
+    }
+
+    /// Recomputes the cycle-to-tick scaling factor for a new SysTick input
+    /// frequency, keeping `now()` continuous across the switch.
+    ///
+    /// Call this at the exact moment the real clock feeding SysTick changes
+    /// (e.g. right after switching onto a newly-locked PLL). The 64-bit tick
+    /// count accumulated so far is preserved: `now()` neither jumps backward
+    /// nor double-counts the in-flight partial period.
+    pub fn reconfigure(&self, new_systick_freq: u64) {
+        critical_section::with(|_| {
+            // Snapshot the tick count and the raw cycle count from the same
+            // coherent `now_cycles()` read, exactly like `adjust_ppb` does —
+            // the raw wrap counters alone have no PendST compensation, so if a
+            // wrap were pending but unserviced here, it would disagree with
+            // `now()`'s view by a full reload period and `now()` would jump
+            // forward right after this reconfigure.
+            let cycles_so_far = self.now_cycles();
+            let ticks_so_far = self.cycles_to_ticks(cycles_so_far);
+
+            // Leave the hardware counter running untouched: `base_cycles`
+            // already anchors the old period's cycles so they aren't
+            // re-scaled under the new multiplier, the same way `adjust_ppb`
+            // re-tunes the slope without ever touching `self.source`.
+            // Clearing the hardware counter (CVR) here would truncate the
+            // in-flight period, but `now_cycles()` treats `reload - VAL` as
+            // "cycles elapsed this period" — clearing VAL to 0 would make
+            // the very next read believe a full period had just elapsed, a
+            // phantom forward jump of up to `reload_value` cycles.
+
+            let new_shift = Self::compute_shift(self.tick_hz, new_systick_freq);
+            let new_multiplier = (self.tick_hz << new_shift) / new_systick_freq;
+
+            self.scaling.write(ScalingState {
+                multiplier: new_multiplier,
+                shift: new_shift,
+                base_ticks: ticks_so_far,
+                base_cycles: cycles_so_far,
+            });
+        });
+    }
+
+    /// Slews the cycle-to-tick scaling factor by `ppb` parts-per-billion,
+    /// without touching the SysTick hardware.
+    ///
+    /// Positive `ppb` speeds ticks up relative to the nominal rate, negative
+    /// slows them down. Intended for a supervisory loop disciplining this
+    /// clock against an external reference (GPS PPS, RTC, host sync) to
+    /// correct accumulated drift instead of letting it grow unbounded.
+    ///
+    /// Like [`Timer::reconfigure`], the new rate takes effect from an
+    /// anchor point so `now()` never jumps backward at the moment of
+    /// re-tuning: only its slope changes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ppb` would drive the multiplier to zero or overflow.
+    pub fn adjust_ppb(&self, ppb: i64) {
+        critical_section::with(|_| {
+            let total_cycles = self.now_cycles();
+            let ticks_so_far = self.cycles_to_ticks(total_cycles);
+
+            let state = self.scaling.read();
+            let adjustment = (state.multiplier as i128) * (ppb as i128) / 1_000_000_000;
+            let new_multiplier = (state.multiplier as i128) + adjustment;
+            assert!(
+                new_multiplier > 0 && new_multiplier <= u64::MAX as i128,
+                "adjust_ppb: ppb={ppb} would drive the multiplier to zero or overflow"
+            );
+
+            self.scaling.write(ScalingState {
+                multiplier: new_multiplier as u64,
+                shift: state.shift,
+                base_ticks: ticks_so_far,
+                base_cycles: total_cycles,
+            });
+        });
+    }
+
+    /// Returns the output tick frequency this timer was configured with.
+    pub const fn tick_hz(&self) -> u64 {
+        self.tick_hz
+    }
+
+    /// Typed counterpart of [`Timer::now()`], returning a `fugit` instant
+    /// instead of a bare tick count.
+    ///
+    /// `TICK_HZ` is carried at the type level rather than read from `self`,
+    /// so it must match the `tick_hz` this timer was constructed with, or
+    /// the returned instant's units will be wrong even though the ticks
+    /// themselves are not.
+    #[cfg(feature = "fugit")]
+    pub fn now_instant<const TICK_HZ: u32>(&self) -> fugit::TimerInstantU64<TICK_HZ> {
+        fugit::TimerInstantU64::from_ticks(self.now())
+    }
+
+    /// Returns the typed duration elapsed since `earlier`, per
+    /// [`Timer::now_instant`].
+    #[cfg(feature = "fugit")]
+    pub fn elapsed_since<const TICK_HZ: u32>(
+        &self,
+        earlier: fugit::TimerInstantU64<TICK_HZ>,
+    ) -> fugit::TimerDurationU64<TICK_HZ> {
+        self.now_instant::<TICK_HZ>() - earlier
+    }
+
     /// Returns the current SysTick counter value.
     pub fn get_syst(&self) -> u32 {
-        #[cfg(test)]
-        return self.current_systick.load(Ordering::SeqCst);
-
-        #[cfg(all(not(test), feature = "cortex-m"))]
-        return cortex_m::peripheral::SYST::get_current();
-
-        #[cfg(all(not(test), not(feature = "cortex-m")))]
-        panic!("This module requires the cortex-m crate to be available");
+        self.source.current()
     }
 
     #[inline(always)]
     pub fn read_systick_countflag(&self) -> bool {
-        #[cfg(test)]
-        {
-            return self
-                .systick_has_wrapped
-                .swap(false, core::sync::atomic::Ordering::SeqCst);
-        }
-
-        // # Safety
-        // Not safe in any way - it's mutating the flag register without having & mut
-        #[cfg(all(not(test), feature = "cortex-m"))]
-        unsafe {
-            // COUNTFLAG is bit 16. Read clears it.
-            const COUNTFLAG: u32 = 1 << 16;
-            let csr = (*cortex_m::peripheral::SYST::PTR).csr.read();
-            (csr & COUNTFLAG) != 0
-        }
-
-        #[cfg(all(not(test), not(feature = "cortex-m")))]
-        {
-            panic!("This module requires the cortex-m crate");
-        }
+        self.source.read_countflag()
     }
 
     /// Checks if the SysTick interrupt is pending.
     pub fn is_systick_pending(&self) -> bool {
-        #[cfg(test)]
-        return self.pendst_is_pending.load(Ordering::SeqCst);
-
-        #[cfg(all(not(test), feature = "cortex-m"))]
-        return cortex_m::peripheral::SCB::is_pendst_pending();
+        self.source.is_pending()
+    }
 
-        #[cfg(all(not(test), not(feature = "cortex-m")))]
-        return false; // Or panic, depending on desired behavior without cortex-m
+    /// Returns a reference to the underlying [`SysTickSource`].
+    ///
+    /// Mainly useful with `S = `[`MockSysTick`] in tests, to drive the
+    /// emulated counter directly.
+    pub fn source(&self) -> &S {
+        &self.source
     }
 
     // Figure out a shift that leads to less precision loss
@@ -200,6 +347,8 @@ impl Timer {
     /// * `reload_value` - The SysTick reload value. Must be between 1 and 2^24-1.
     ///   This determines how many cycles occur between interrupts.
     /// * `systick_freq` - The frequency of the SysTick counter in Hz (typically CPU frequency)
+    /// * `source` - The [`SysTickSource`] this timer reads; [`CortexMSysTick`] on real
+    ///   hardware, [`MockSysTick`] in tests.
     ///
     /// # Panics
     ///
@@ -209,11 +358,11 @@ impl Timer {
     /// # Examples
     ///
     /// ```
-    /// # use systick_timer::Timer;
+    /// # use systick_timer::{CortexMSysTick, Timer};
     /// // Create a millisecond-resolution timer on a 48MHz CPU with reload value of 47,999
-    /// let timer = Timer::new(1000, 47_999, 48_000_000);
+    /// let timer = Timer::new(1000, 47_999, 48_000_000, CortexMSysTick);
     /// ```
-    pub const fn new(tick_hz: u64, reload_value: u32, systick_freq: u64) -> Self {
+    pub const fn new(tick_hz: u64, reload_value: u32, systick_freq: u64, source: S) -> Self {
         if reload_value > (1 << 24) - 1 {
             panic!("Reload value too large");
         }
@@ -229,16 +378,20 @@ impl Timer {
             inner_wraps: AtomicU32::new(0),
             outer_wraps: AtomicU32::new(0),
             reload_value,
-            multiplier,
-            shift,
-            #[cfg(test)]
-            current_systick: AtomicU32::new(0),
-            #[cfg(test)]
-            systick_has_wrapped: AtomicBool::new(false),
-            #[cfg(test)]
-            after_v1_hook: None,
-            #[cfg(test)]
-            pendst_is_pending: AtomicBool::new(false),
+            tick_hz,
+            scaling: SeqLock::new(ScalingState {
+                multiplier,
+                shift,
+                base_ticks: 0,
+                base_cycles: 0,
+            }),
+            source,
+            #[cfg(feature = "embassy-time-driver")]
+            alarms: [const { embassy::AlarmSlot::new() }; embassy::ALARM_COUNT],
+            #[cfg(feature = "async-sleep")]
+            wheel: critical_section::Mutex::new(core::cell::RefCell::new(wheel::Wheel::new())),
+            #[cfg(feature = "periodic-events")]
+            periodic: [const { periodic::PeriodicSlot::new() }; periodic::PERIODIC_COUNT],
         }
     }
 
@@ -293,50 +446,30 @@ impl Timer {
     }
 }
 
-impl Timer {
-    // -------- test-only helpers ----------
-    #[cfg(test)]
-    pub fn set_syst(&self, value: u32) {
-        debug_assert!(
-            value <= self.reload_value,
-            "set_syst: value {} exceeds reload {}",
-            value,
-            self.reload_value
-        );
-        self.current_systick.store(value, Ordering::SeqCst);
-    }
-
-    #[cfg(test)]
-    pub fn set_systick_has_wrapped(&self, val: bool) {
-        self.systick_has_wrapped.store(val, Ordering::SeqCst);
-    }
-
-    #[cfg(test)]
-    pub fn set_after_v1_hook(&mut self, hook: Option<fn(&Timer)>) {
-        self.after_v1_hook = hook;
-    }
-
-    #[cfg(test)]
-    pub fn set_pendst_pending(&self, val: bool) {
-        self.pendst_is_pending.store(val, Ordering::SeqCst);
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    // All tests below drive `Timer<MockSysTick>`, shadowing the generic
+    // `Timer` brought in by the glob import above, so existing call sites
+    // below don't need to spell out the type parameter everywhere.
+    type Timer = super::Timer<MockSysTick>;
+
+    fn new_timer(tick_hz: u64, reload_value: u32, systick_freq: u64) -> Timer {
+        Timer::new(tick_hz, reload_value, systick_freq, MockSysTick::new(0))
+    }
+
     #[test]
     #[should_panic]
     fn test_zero_systick_freq() {
-        Timer::new(1000, 5, 0);
+        new_timer(1000, 5, 0);
     }
 
     #[test]
     fn test_timer_new() {
-        let mut timer = Timer::new(1000, 5, 12_000);
+        let timer = new_timer(1000, 5, 12_000);
         timer.inner_wraps.store(4, Ordering::Relaxed); // 4 interrupts = 24 cycles
-        timer.set_syst(3); // Start of next period
+        timer.source().set_current(3); // Start of next period
         assert_eq!(timer.now(), 2); // Should be ~2 ticks
     }
 
@@ -349,7 +482,7 @@ mod tests {
 
     #[test]
     fn test_timer_initial_state() {
-        let timer = Timer::new(1000, 5, 12_000);
+        let timer = new_timer(1000, 5, 12_000);
         assert_eq!(timer.now(), 0);
     }
 
@@ -359,16 +492,16 @@ mod tests {
     impl<const RELOAD: u32> TestTimer<RELOAD> {
         fn new(tick_hz: u64, systick_freq: u64) -> Self {
             Self {
-                timer: Timer::new(tick_hz, RELOAD, systick_freq),
+                timer: new_timer(tick_hz, RELOAD, systick_freq),
             }
         }
         fn interrupt(&mut self) {
             self.timer.systick_handler();
-            self.timer.set_syst(RELOAD);
+            self.timer.source().set_current(RELOAD);
         }
         fn set_tick(&mut self, tick: u32) -> u64 {
             assert!(tick <= RELOAD);
-            self.timer.set_syst(tick);
+            self.timer.source().set_current(tick);
             self.timer.now()
         }
     }
@@ -415,7 +548,7 @@ mod tests {
         // Set up for outer_wraps overflow
         timer.timer.inner_wraps.store(u32::MAX, Ordering::Relaxed);
         timer.timer.outer_wraps.store(u32::MAX, Ordering::Relaxed);
-        timer.timer.set_syst(5);
+        timer.timer.source().set_current(5);
 
         // One more interrupt should wrap outer_wraps
         timer.interrupt();
@@ -443,6 +576,32 @@ mod tests {
         assert_eq!(timer.set_tick(5), 5); // Should get 5 complete ticks
     }
 
+    #[test]
+    fn test_now_equals_cycles_to_ticks_of_now_cycles() {
+        // Exercises the same matching/2x/extreme rate ratios as the other
+        // scaling tests above, asserting the round-trip identity
+        // `now() == cycles_to_ticks(now_cycles())` instead of a fixed tick
+        // count, since that's the property this refactor must preserve.
+        let rates = [(1000, 1000), (2000, 1000), (1000, 2000), (1_000_000, 1000)];
+
+        for (tick_hz, systick_freq) in rates {
+            let mut timer = TestTimer::<5>::new(tick_hz, systick_freq);
+            for tick in [5, 4, 0] {
+                timer.set_tick(tick);
+                assert_eq!(
+                    timer.timer.now(),
+                    timer.timer.cycles_to_ticks(timer.timer.now_cycles())
+                );
+            }
+            timer.interrupt();
+            timer.set_tick(5);
+            assert_eq!(
+                timer.timer.now(),
+                timer.timer.cycles_to_ticks(timer.timer.now_cycles())
+            );
+        }
+    }
+
     #[test]
     fn test_boundary_conditions() {
         // Test with minimum reload value
@@ -474,7 +633,7 @@ mod tests {
     fn test_interrupt_race() {
         let mut timer = TestTimer::<5>::new(1000, 1000);
         timer.interrupt();
-        timer.timer.set_syst(3);
+        timer.timer.source().set_current(3);
         let t1 = timer.timer.now();
         timer.interrupt();
         let t2 = timer.timer.now();
@@ -501,7 +660,7 @@ mod tests {
         // TICK_RESOLUTION: 10_000_000 (tick_hz)
         // reload_value: 0xFFFFFF (16,777,215)
         // systick_freq: 100_000_000
-        let timer = Timer::new(10_000_000, 0xFFFFFF, 100_000_000);
+        let timer = new_timer(10_000_000, 0xFFFFFF, 100_000_000);
 
         let total_interrupts = 2560u64;
         let outer = (total_interrupts >> 32) as u32;
@@ -518,16 +677,16 @@ mod tests {
     #[test]
     fn test_monotonicity_around_wrap() {
         const RELOAD: u32 = 100;
-        let timer = Timer::new(1_000, RELOAD, 1_000);
+        let timer = new_timer(1_000, RELOAD, 1_000);
 
         // 1. Time right before the wrap
-        timer.set_syst(1);
+        timer.source().set_current(1);
         let t1 = timer.now();
 
         // 2. Simulate the hardware wrap:
         //    - The ISR has NOT run yet, but the pending bit is set.
-        timer.set_syst(RELOAD);
-        timer.set_pendst_pending(true);
+        timer.source().set_current(RELOAD);
+        timer.source().set_pending(true);
 
         // 3. Time right after the wrap
         let t2 = timer.now();
@@ -552,18 +711,18 @@ mod tests {
     #[test]
     fn test_monotonicity_between_interrupts() {
         const RELOAD: u32 = 100;
-        let timer = Timer::new(1_000, RELOAD, 1_000);
+        let timer = new_timer(1_000, RELOAD, 1_000);
 
         // Set the counter to the reload value, no wraps yet.
-        timer.set_syst(RELOAD);
+        timer.source().set_current(RELOAD);
         let t1 = timer.now();
 
         // Simulate time passing by decrementing the hardware counter.
-        timer.set_syst(RELOAD / 2);
+        timer.source().set_current(RELOAD / 2);
         let t2 = timer.now();
 
         // Decrement again.
-        timer.set_syst(0);
+        timer.source().set_current(0);
         let t3 = timer.now();
 
         // Assert that time is always moving forward.
@@ -586,17 +745,17 @@ mod tests {
         // 3. Multiple calls to now() are made from the higher-priority context.
         // 4. All calls must see the pending wrap and report monotonic time.
 
-        let timer = Timer::new(1_000, RELOAD, 1_000); // 1 tick per cycle
+        let timer = new_timer(1_000, RELOAD, 1_000); // 1 tick per cycle
 
         // State 1: Right before a wrap.
-        timer.set_syst(1);
+        timer.source().set_current(1);
         let t1 = timer.now();
         assert_eq!(t1, 100 - 1);
 
         // State 2: Hardware wraps, ISR is pended but does not run.
         // We manually simulate this state.
-        timer.set_pendst_pending(true);
-        timer.set_syst(RELOAD - 10); // Timer has wrapped and counted down a bit.
+        timer.source().set_pending(true);
+        timer.source().set_current(RELOAD - 10); // Timer has wrapped and counted down a bit.
 
         // First call to now() after the wrap. It must see the pending bit.
         let t2 = timer.now();
@@ -610,7 +769,7 @@ mod tests {
         );
 
         // State 3: More time passes, ISR is still starved.
-        timer.set_syst(RELOAD - 20);
+        timer.source().set_current(RELOAD - 20);
 
         // Second call to now(). It must still see the pending bit.
         let t3 = timer.now();
@@ -624,7 +783,7 @@ mod tests {
         );
 
         // State 4: The ISR finally runs, clearing the pending bit and incrementing wraps.
-        timer.set_pendst_pending(false);
+        timer.source().set_pending(false);
         timer.systick_handler(); // This increments inner_wraps to 1.
 
         // Third call to now(). It should now use the updated wrap counter.
@@ -641,6 +800,171 @@ mod tests {
     // The old tests for value-jump and COUNTFLAG are no longer relevant
     // as the core logic has been replaced. The new test above provides
     // superior coverage for the most critical race condition.
+
+    #[test]
+    fn test_adjust_ppb_preserves_continuity_and_changes_slope() {
+        let timer = new_timer(1_000_000, RELOAD, 1_000_000); // 1 tick per cycle
+
+        timer.source().set_current(50);
+        let before = timer.now();
+
+        // Speed the clock up by 10% (100_000_000 ppb). `now()` must not jump
+        // backward at the moment of the re-tune.
+        timer.adjust_ppb(100_000_000);
+        let at_adjust = timer.now();
+        assert_eq!(
+            at_adjust, before,
+            "adjust_ppb must not move now() at the instant it's applied"
+        );
+
+        // After the adjustment, the same cycle delta yields more ticks than
+        // it would have before, since the multiplier increased.
+        timer.source().set_current(0);
+        let after_sped_up = timer.now() - at_adjust;
+        assert!(
+            after_sped_up > 50,
+            "sped-up clock should report more than 50 ticks for 50 cycles, got {after_sped_up}"
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_adjust_ppb_rejects_negative_overflow() {
+        let timer = new_timer(1_000_000, RELOAD, 1_000_000);
+        // -1_000_000_000 ppb (-100%) would drive the multiplier to zero.
+        timer.adjust_ppb(-1_000_000_000);
+    }
+
+    #[test]
+    fn test_reconfigure_with_pending_wrap_does_not_skip_a_period() {
+        // 1 tick per cycle, reload period = 101 cycles.
+        let timer = new_timer(1_000_000, RELOAD, 1_000_000);
+
+        // Hardware has just wrapped (VAL reloaded to RELOAD) but the ISR
+        // hasn't run yet, so `inner_wraps`/`outer_wraps` (what a snapshot of
+        // the raw wrap counters alone would use) are stale by one wrap
+        // relative to what `now()` reports via its PendST compensation.
+        timer.source().set_pending(true);
+        timer.source().set_current(RELOAD);
+
+        timer.reconfigure(1_000_000);
+        let at_reconfigure = timer.now();
+
+        // If `reconfigure`'s cycle anchor disagreed with its tick anchor
+        // about whether that pending wrap had happened, `now()` would jump
+        // forward by an entire extra `reload_value + 1` cycles right here.
+        // Anchoring both from the same `now_cycles()` snapshot, and leaving
+        // the hardware counter untouched, means a `now()` read immediately
+        // after `reconfigure` reproduces exactly the snapshot it anchored
+        // from: no jump at all, not even a sub-period one.
+        assert_eq!(
+            at_reconfigure,
+            101,
+            "reconfigure must not lose a pending wrap: expected 101, got {at_reconfigure}"
+        );
+    }
+
+    #[cfg(feature = "periodic-events")]
+    #[test]
+    fn test_register_periodic_fires_once_per_poll_and_skips_missed_periods() {
+        use core::sync::atomic::AtomicU32;
+
+        static FIRE_COUNT: AtomicU32 = AtomicU32::new(0);
+        fn on_fire() {
+            FIRE_COUNT.fetch_add(1, Ordering::SeqCst);
+        }
+
+        // 1 tick per cycle, reload period = 101 cycles.
+        let mut timer = TestTimer::<100>::new(1_000, 1_000);
+        timer
+            .timer
+            .register_periodic(10, on_fire)
+            .expect("slot available");
+
+        // One full reload period (101 ticks) has elapsed, which covers 10
+        // ten-tick intervals. The callback must still only fire once per
+        // `poll_periodic` call, not once per missed interval.
+        timer.set_tick(0);
+        timer.interrupt();
+        assert_eq!(FIRE_COUNT.load(Ordering::SeqCst), 1);
+
+        timer.set_tick(0);
+        timer.interrupt();
+        assert_eq!(FIRE_COUNT.load(Ordering::SeqCst), 2);
+    }
+
+    #[cfg(feature = "periodic-events")]
+    #[test]
+    fn test_register_oneshot_fires_once_then_disarms() {
+        use core::sync::atomic::AtomicU32;
+
+        static FIRE_COUNT: AtomicU32 = AtomicU32::new(0);
+        fn on_fire() {
+            FIRE_COUNT.fetch_add(1, Ordering::SeqCst);
+        }
+
+        let mut timer = TestTimer::<100>::new(1_000, 1_000);
+        timer
+            .timer
+            .register_oneshot(10, on_fire)
+            .expect("slot available");
+
+        timer.set_tick(0);
+        timer.interrupt();
+        assert_eq!(FIRE_COUNT.load(Ordering::SeqCst), 1);
+
+        timer.set_tick(0);
+        timer.interrupt();
+        assert_eq!(
+            FIRE_COUNT.load(Ordering::SeqCst),
+            1,
+            "one-shot callback must not fire a second time"
+        );
+    }
+
+    #[cfg(feature = "periodic-events")]
+    #[test]
+    fn test_register_periodic_rejects_zero_interval() {
+        // `0` is the one-shot sentinel internally; registering it as a
+        // period must be rejected rather than silently collapsing into a
+        // one-shot that fires once and disarms.
+        let timer = TestTimer::<100>::new(1_000, 1_000);
+        assert!(timer.timer.register_periodic(0, || {}).is_none());
+    }
+
+    #[cfg(feature = "embassy-time-driver")]
+    #[test]
+    fn test_embassy_alarm_fires_on_systick_handler() {
+        use core::sync::atomic::AtomicBool;
+        use embassy_time_driver::Driver;
+
+        static FIRED: AtomicBool = AtomicBool::new(false);
+        fn on_fire(ctx: *mut ()) {
+            let flag = unsafe { &*(ctx as *const AtomicBool) };
+            flag.store(true, Ordering::SeqCst);
+        }
+
+        let timer = new_timer(1_000, RELOAD, 1_000); // 1 tick per cycle
+        // Start at the top of the first period so `now()` is 0.
+        timer.source().set_current(RELOAD);
+        // SAFETY: the only alarm allocated on this `Timer` in this test.
+        let alarm = unsafe { Driver::allocate_alarm(&timer) }.expect("alarm slot available");
+        Driver::set_alarm_callback(&timer, alarm, on_fire, &FIRED as *const _ as *mut ());
+
+        assert!(Driver::set_alarm(&timer, alarm, 5));
+        // A deadline that has already passed is rejected, not armed.
+        assert!(!Driver::set_alarm(&timer, alarm, 0));
+
+        // Advance past the armed deadline and let the wrap interrupt path
+        // (`poll_alarms`, called from `systick_handler`) notice it.
+        timer.source().set_current(RELOAD - 5);
+        timer.systick_handler();
+
+        assert!(
+            FIRED.load(Ordering::SeqCst),
+            "alarm callback should have fired once its deadline passed"
+        );
+    }
 }
 
 #[cfg(test)]
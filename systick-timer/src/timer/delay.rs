@@ -0,0 +1,93 @@
+// SPDX-License-Identifier: Apache-2.0
+//! `embedded-hal` delay implementations driven by [`Timer::now()`].
+//!
+//! The blocking impls busy-wait on the same tick source the rest of the
+//! crate uses for timekeeping, so a delay straddling a SysTick wrap is
+//! handled correctly without a second hardware timer. When both
+//! `embedded-hal-async` and `async-sleep` are also enabled, an
+//! `embedded-hal-async` impl is provided that yields
+//! to the executor via the timing wheel instead of spinning.
+
+use super::{SysTickSource, Timer};
+
+impl<S: SysTickSource> Timer<S> {
+    /// Converts a duration in nanoseconds to a tick count, using a 128-bit
+    /// intermediate to avoid overflow at high tick rates.
+    ///
+    /// Panics if the requested delay's tick count doesn't fit in a `u64`,
+    /// rather than silently truncating to a far shorter delay.
+    fn ticks_for_nanos(&self, ns: u64) -> u64 {
+        let ticks = (ns as u128 * self.tick_hz() as u128) / 1_000_000_000;
+        u64::try_from(ticks).expect("delay exceeds the representable tick range")
+    }
+
+    /// Busy-waits until `now()` has advanced by at least `ticks`.
+    fn spin_ticks(&self, ticks: u64) {
+        let start = self.now();
+        while self.now().wrapping_sub(start) < ticks {
+            #[cfg(feature = "cortex-m")]
+            cortex_m::asm::nop();
+        }
+    }
+}
+
+impl<S: SysTickSource> embedded_hal::delay::DelayNs for &Timer<S> {
+    fn delay_ns(&mut self, ns: u32) {
+        self.spin_ticks(self.ticks_for_nanos(ns as u64));
+    }
+}
+
+/// Non-blocking delay that yields instead of spinning, for use in async
+/// executors that already poll this timer's wheel (see [`super::wheel`]).
+///
+/// Unlike [`embedded_hal::delay::DelayNs`] above, this does not busy-wait:
+/// it registers with the same timing wheel `Sleep`/`timeout` use, so the
+/// executor can run other tasks while the delay elapses.
+#[cfg(all(feature = "embedded-hal-async", feature = "async-sleep"))]
+impl<S: SysTickSource> embedded_hal_async::delay::DelayNs for &Timer<S> {
+    async fn delay_ns(&mut self, ns: u32) {
+        let ticks = self.ticks_for_nanos(ns as u64);
+        self.sleep(ticks).await;
+    }
+}
+
+#[cfg(feature = "embedded-hal-0_2")]
+mod embedded_hal_0_2_compat {
+    use super::{SysTickSource, Timer};
+
+    impl<S: SysTickSource> embedded_hal_0_2::blocking::delay::DelayMs<u32> for &Timer<S> {
+        fn delay_ms(&mut self, ms: u32) {
+            self.spin_ticks(self.ticks_for_nanos(ms as u64 * 1_000_000));
+        }
+    }
+
+    impl<S: SysTickSource> embedded_hal_0_2::blocking::delay::DelayMs<u16> for &Timer<S> {
+        fn delay_ms(&mut self, ms: u16) {
+            embedded_hal_0_2::blocking::delay::DelayMs::<u32>::delay_ms(self, ms as u32);
+        }
+    }
+
+    impl<S: SysTickSource> embedded_hal_0_2::blocking::delay::DelayMs<u8> for &Timer<S> {
+        fn delay_ms(&mut self, ms: u8) {
+            embedded_hal_0_2::blocking::delay::DelayMs::<u32>::delay_ms(self, ms as u32);
+        }
+    }
+
+    impl<S: SysTickSource> embedded_hal_0_2::blocking::delay::DelayUs<u32> for &Timer<S> {
+        fn delay_us(&mut self, us: u32) {
+            self.spin_ticks(self.ticks_for_nanos(us as u64 * 1_000));
+        }
+    }
+
+    impl<S: SysTickSource> embedded_hal_0_2::blocking::delay::DelayUs<u16> for &Timer<S> {
+        fn delay_us(&mut self, us: u16) {
+            embedded_hal_0_2::blocking::delay::DelayUs::<u32>::delay_us(self, us as u32);
+        }
+    }
+
+    impl<S: SysTickSource> embedded_hal_0_2::blocking::delay::DelayUs<u8> for &Timer<S> {
+        fn delay_us(&mut self, us: u8) {
+            embedded_hal_0_2::blocking::delay::DelayUs::<u32>::delay_us(self, us as u32);
+        }
+    }
+}
@@ -0,0 +1,206 @@
+// SPDX-License-Identifier: Apache-2.0
+//! `embassy-time-driver` integration.
+//!
+//! SysTick has no hardware compare register, so alarms are tracked in
+//! software: each [`Timer`] carries a small fixed pool of alarm slots that
+//! [`Timer::systick_handler`] scans on every reload interrupt, firing (and
+//! disarming) any slot whose target tick has passed. Because of this, alarm
+//! granularity is one SysTick reload period by default.
+
+use super::{SysTickSource, Timer};
+use core::cell::Cell;
+use core::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use critical_section::Mutex;
+use embassy_time_driver::{AlarmHandle, Driver};
+
+/// Number of alarms that can be allocated concurrently.
+pub const ALARM_COUNT: usize = 4;
+
+type Callback = (fn(*mut ()), *mut ());
+
+pub(crate) struct AlarmSlot {
+    allocated: AtomicBool,
+    target: AtomicU64,
+    callback: Mutex<Cell<Option<Callback>>>,
+}
+
+impl AlarmSlot {
+    pub(crate) const fn new() -> Self {
+        Self {
+            allocated: AtomicBool::new(false),
+            target: AtomicU64::new(u64::MAX),
+            callback: Mutex::new(Cell::new(None)),
+        }
+    }
+}
+
+impl<S: SysTickSource> Timer<S> {
+    /// Scans armed alarms and fires (disarming) any whose target tick has
+    /// already passed.
+    ///
+    /// Called from [`Timer::systick_handler`] after the wrap counters are
+    /// updated, so `now()` already reflects the interrupt that triggered
+    /// this scan.
+    pub(super) fn poll_alarms(&self) {
+        let now = self.now();
+        for slot in &self.alarms {
+            if !slot.allocated.load(Ordering::SeqCst) {
+                continue;
+            }
+            let target = slot.target.load(Ordering::SeqCst);
+            if target <= now {
+                // Disarm before invoking the callback so a re-entrant
+                // `set_alarm` from inside the callback is not immediately
+                // clobbered by this scan.
+                slot.target.store(u64::MAX, Ordering::SeqCst);
+                let callback = critical_section::with(|cs| slot.callback.borrow(cs).get());
+                if let Some((func, ctx)) = callback {
+                    func(ctx);
+                }
+            }
+        }
+
+        #[cfg(feature = "embassy-time-driver-fast-wake")]
+        self.reprogram_reload_for_nearest_alarm(now);
+    }
+
+    /// Smallest armed alarm target strictly after `now`, if any.
+    fn nearest_alarm_after(&self, now: u64) -> Option<u64> {
+        self.alarms
+            .iter()
+            .filter(|slot| slot.allocated.load(Ordering::SeqCst))
+            .map(|slot| slot.target.load(Ordering::SeqCst))
+            // `u64::MAX` marks a disarmed slot.
+            .filter(|&target| target > now && target != u64::MAX)
+            .min()
+    }
+
+    /// Ticks remaining until the nearest armed alarm, or `None` if no alarm
+    /// is currently armed.
+    ///
+    /// Intended for power-management code deciding whether it's safe to
+    /// enter a low-power mode that would miss the next SysTick reload: if
+    /// this returns `Some(ticks)` with `ticks` smaller than the wake-up
+    /// latency of that mode, the caller should stay awake instead.
+    pub fn time_until_next_alarm(&self) -> Option<u64> {
+        let now = self.now();
+        self.nearest_alarm_after(now).map(|target| target - now)
+    }
+
+    /// Shortens `SYST_RVR` so the reload *after next* fires close to the
+    /// nearest pending alarm, instead of waiting up to a full reload period.
+    ///
+    /// CVR reloads from RVR synchronously with the wrap event, before
+    /// `systick_handler` ever runs, so by the time [`Timer::poll_alarms`]
+    /// calls this post-wrap, CVR has already reloaded for the period that's
+    /// now running — narrowing RVR here only takes effect one reload later
+    /// than that. [`Driver::set_alarm`] below also calls this immediately
+    /// when a new alarm is armed, which is usually what actually narrows
+    /// the *next* interrupt: RVR takes effect at the next wrap regardless
+    /// of when during the current period it's written, so arming an alarm
+    /// while a period is already in flight narrows the very reload that
+    /// ends it. Calling this only from the post-wrap path, as before, would
+    /// always cost one extra stale full period first.
+    ///
+    /// Restores the configured reload once no alarm is nearer than one full
+    /// period away. This does not change the tick scaling, since
+    /// `systick_handler` still accounts for a full `reload_value + 1`
+    /// cycles per wrap — so while a reload is narrowed, `now()` will run
+    /// fast by the shortfall until the narrowing lifts, same as any other
+    /// untracked change to the raw reload; bounded to at most the one
+    /// narrowed period before `poll_alarms` finds the due alarm and this
+    /// restores the full period.
+    #[cfg(feature = "embassy-time-driver-fast-wake")]
+    fn reprogram_reload_for_nearest_alarm(&self, now: u64) {
+        let nearest = self.nearest_alarm_after(now);
+
+        let full_period = self.reload_value;
+        let next_reload = match nearest {
+            Some(target) => {
+                let ticks_away = target - now;
+                // Inverse of the `now()` scaling: cycles = (ticks << shift) / multiplier.
+                let state = self.scaling.read();
+                let cycles_away = ((ticks_away as u128) << state.shift) / state.multiplier as u128;
+                // RVR = cycles - 1; capped at the configured reload, and kept
+                // at least 1 since RVR = 0 never wraps.
+                (cycles_away.min(full_period as u128 + 1).max(2) - 1) as u32
+            }
+            None => full_period,
+        };
+
+        // SAFETY: only touches SYST_RVR, which is safe to reprogram from any
+        // context; the worst case is a slightly early or late reload.
+        #[cfg(feature = "cortex-m")]
+        unsafe {
+            (*cortex_m::peripheral::SYST::PTR)
+                .rvr
+                .write(next_reload);
+        }
+    }
+}
+
+// SAFETY: all shared state is behind atomics or a `critical_section::Mutex`.
+unsafe impl<S: SysTickSource> Driver for Timer<S> {
+    fn now(&self) -> u64 {
+        Timer::now(self)
+    }
+
+    unsafe fn allocate_alarm(&self) -> Option<AlarmHandle> {
+        for (index, slot) in self.alarms.iter().enumerate() {
+            if slot
+                .allocated
+                .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+                .is_ok()
+            {
+                // SAFETY: `index` is unique among currently-allocated alarms.
+                return Some(unsafe { AlarmHandle::new(index as u8) });
+            }
+        }
+        None
+    }
+
+    fn set_alarm_callback(&self, alarm: AlarmHandle, callback: fn(*mut ()), ctx: *mut ()) {
+        let slot = &self.alarms[alarm.id() as usize];
+        critical_section::with(|cs| slot.callback.borrow(cs).set(Some((callback, ctx))));
+    }
+
+    fn set_alarm(&self, alarm: AlarmHandle, timestamp: u64) -> bool {
+        let now = self.now();
+        if timestamp <= now {
+            // Deadline already passed: don't arm, let embassy retry.
+            return false;
+        }
+        self.alarms[alarm.id() as usize]
+            .target
+            .store(timestamp, Ordering::SeqCst);
+
+        // Narrow the reload now, while this period is still in flight,
+        // rather than waiting for `poll_alarms` to notice on the next wrap
+        // (see `reprogram_reload_for_nearest_alarm`'s doc comment for why
+        // that alone would cost an extra stale full period).
+        #[cfg(feature = "embassy-time-driver-fast-wake")]
+        self.reprogram_reload_for_nearest_alarm(now);
+
+        true
+    }
+}
+
+/// Declares a `static` [`Timer`] and binds it as the global `embassy-time`
+/// driver, mirroring `embassy_time_driver::time_driver_impl!`.
+///
+/// ```ignore
+/// systick_timer::time_driver_impl!(static TIMER: 1_000_000, reload = 47_999, core_hz = 48_000_000);
+/// ```
+#[macro_export]
+macro_rules! time_driver_impl {
+    (static $name:ident: $tick_hz:expr, reload = $reload:expr, core_hz = $core_hz:expr) => {
+        ::embassy_time_driver::time_driver_impl!(
+            static $name: $crate::Timer<$crate::CortexMSysTick> = $crate::Timer::new(
+                $tick_hz,
+                $reload,
+                $core_hz,
+                $crate::CortexMSysTick,
+            )
+        );
+    };
+}
@@ -0,0 +1,135 @@
+// SPDX-License-Identifier: Apache-2.0
+//! Periodic and one-shot software callbacks driven from the SysTick ISR.
+//!
+//! Unlike [`super::wheel`]'s async `Sleep`/`timeout` futures, these are
+//! plain `fn()` callbacks with no executor involved — closer to the
+//! `CountDown`/`Periodic`/`listen(Event::Update)` pattern HALs build on top
+//! of a hardware timer, except here SysTick itself is the only hardware
+//! timer needed. [`Timer::poll_periodic`] is called from
+//! [`Timer::systick_handler`] after the wrap counters are updated, so a
+//! callback always sees a `now()` that already accounts for the interrupt
+//! that triggered it.
+
+use super::{SysTickSource, Timer};
+use core::cell::Cell;
+use core::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use critical_section::Mutex;
+
+/// Number of periodic/one-shot callbacks that can be registered concurrently.
+pub const PERIODIC_COUNT: usize = 4;
+
+pub(crate) struct PeriodicSlot {
+    allocated: AtomicBool,
+    next_fire: AtomicU64,
+    /// Reschedule interval in ticks; `0` marks a one-shot slot, which
+    /// disarms instead of rescheduling once it fires.
+    interval: AtomicU64,
+    callback: Mutex<Cell<Option<fn()>>>,
+}
+
+impl PeriodicSlot {
+    pub(crate) const fn new() -> Self {
+        Self {
+            allocated: AtomicBool::new(false),
+            next_fire: AtomicU64::new(u64::MAX),
+            interval: AtomicU64::new(0),
+            callback: Mutex::new(Cell::new(None)),
+        }
+    }
+}
+
+/// A callback registered via [`Timer::register_periodic`] or
+/// [`Timer::register_oneshot`].
+///
+/// There is currently no API to cancel a registration early.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PeriodicHandle(u8);
+
+impl<S: SysTickSource> Timer<S> {
+    fn register_slot(
+        &self,
+        first_delay: u64,
+        interval: u64,
+        callback: fn(),
+    ) -> Option<PeriodicHandle> {
+        for (index, slot) in self.periodic.iter().enumerate() {
+            if slot
+                .allocated
+                .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+                .is_ok()
+            {
+                slot.next_fire
+                    .store(self.now().saturating_add(first_delay), Ordering::SeqCst);
+                slot.interval.store(interval, Ordering::SeqCst);
+                critical_section::with(|cs| slot.callback.borrow(cs).set(Some(callback)));
+                return Some(PeriodicHandle(index as u8));
+            }
+        }
+        None
+    }
+
+    /// Registers `callback` to run every `interval_ticks` ticks, starting
+    /// one interval from now.
+    ///
+    /// Returns `None` if every slot is already in use, or if `interval_ticks`
+    /// is `0` (that value is reserved as the one-shot sentinel internally,
+    /// and firing on every tick isn't a meaningful period to ask for anyway).
+    pub fn register_periodic(
+        &self,
+        interval_ticks: u64,
+        callback: fn(),
+    ) -> Option<PeriodicHandle> {
+        if interval_ticks == 0 {
+            return None;
+        }
+        self.register_slot(interval_ticks, interval_ticks, callback)
+    }
+
+    /// Registers `callback` to run exactly once, `delay_ticks` ticks from
+    /// now, then disarm.
+    ///
+    /// Returns `None` if every slot is already in use.
+    pub fn register_oneshot(&self, delay_ticks: u64, callback: fn()) -> Option<PeriodicHandle> {
+        self.register_slot(delay_ticks, 0, callback)
+    }
+
+    /// Fires (and reschedules or disarms) any registered callback whose
+    /// `next_fire` has passed.
+    ///
+    /// Called from [`Timer::systick_handler`]; safe to call more or less
+    /// often than once per wrap.
+    pub(super) fn poll_periodic(&self) {
+        let now = self.now();
+        for slot in &self.periodic {
+            if !slot.allocated.load(Ordering::SeqCst) {
+                continue;
+            }
+            let next_fire = slot.next_fire.load(Ordering::SeqCst);
+            if next_fire > now {
+                continue;
+            }
+
+            let interval = slot.interval.load(Ordering::SeqCst);
+            if interval == 0 {
+                // Disarm before invoking so a re-entrant `register_*` call
+                // from inside the callback doesn't collide with this slot.
+                slot.allocated.store(false, Ordering::SeqCst);
+            } else {
+                // Skip forward by however many whole intervals have already
+                // elapsed, so a long ISR-latency burst never schedules a
+                // backlog of catch-up calls.
+                let elapsed = now - next_fire;
+                let missed_periods = elapsed / interval;
+                slot.next_fire.store(
+                    next_fire + (missed_periods + 1) * interval,
+                    Ordering::SeqCst,
+                );
+            }
+
+            let callback = critical_section::with(|cs| slot.callback.borrow(cs).get());
+            if let Some(func) = callback {
+                func();
+            }
+        }
+    }
+}
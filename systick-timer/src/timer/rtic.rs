@@ -0,0 +1,94 @@
+// SPDX-License-Identifier: Apache-2.0
+//! `rtic-monotonic` integration.
+//!
+//! SysTick has no hardware compare register, so [`SystickMonotonic`] keeps
+//! no compare state of its own and leans entirely on RTIC's own dispatch
+//! loop (driven by the SysTick interrupt) to notice when `now()` has
+//! reached the next scheduled instant; RTIC tracks that instant itself and
+//! only calls [`Monotonic::set_compare`] as a hint for a hardware compare
+//! register this timer doesn't have, so [`SystickMonotonic::set_compare`]
+//! is a no-op, same as [`SystickMonotonic::clear_compare_flag`]. The reload
+//! period is left untouched, so a task scheduled for a given instant may
+//! fire up to one full SysTick reload period late.
+//! `enable_timer`/`disable_timer` toggle `SYST_CSR.TICKINT` directly, since
+//! `SystickMonotonic` only holds a `Timer` reference rather than the `SYST`
+//! peripheral itself. RTIC owns the SysTick interrupt when bound via
+//! `#[monotonic(binds = SysTick, ...)]`, so [`SystickMonotonic::on_interrupt`]
+//! drives [`Timer::systick_handler`] itself rather than assuming a separate
+//! `#[exception] fn SysTick()` already did.
+
+use super::{SysTickSource, Timer};
+use fugit::{TimerDurationU64, TimerInstantU64};
+use rtic_monotonic::Monotonic;
+
+/// A [`rtic_monotonic::Monotonic`] built on a SysTick-backed [`Timer`].
+///
+/// `TICK_HZ` is the tick resolution `timer` was configured with; it must
+/// match the `tick_hz` passed to [`Timer::new`] or the reported instants
+/// will be scaled wrong.
+pub struct SystickMonotonic<S: SysTickSource, const TICK_HZ: u32> {
+    timer: &'static Timer<S>,
+}
+
+impl<S: SysTickSource, const TICK_HZ: u32> SystickMonotonic<S, TICK_HZ> {
+    /// Wraps an already-configured `timer` as an RTIC monotonic.
+    pub const fn new(timer: &'static Timer<S>) -> Self {
+        Self { timer }
+    }
+}
+
+impl<S: SysTickSource, const TICK_HZ: u32> Monotonic for SystickMonotonic<S, TICK_HZ> {
+    type Instant = TimerInstantU64<TICK_HZ>;
+    type Duration = TimerDurationU64<TICK_HZ>;
+
+    const DISABLE_INTERRUPT_ON_EMPTY_QUEUE: bool = false;
+
+    fn now(&mut self) -> Self::Instant {
+        Self::Instant::from_ticks(self.timer.now())
+    }
+
+    fn zero() -> Self::Instant {
+        Self::Instant::from_ticks(0)
+    }
+
+    unsafe fn reset(&mut self) {
+        // The `Timer` is started separately via `Timer::start`; nothing
+        // further is required here since SysTick is already free-running.
+    }
+
+    fn set_compare(&mut self, _instant: Self::Instant) {
+        // No hardware compare register to program: RTIC tracks the next
+        // due instant itself and just compares it against `now()` on every
+        // dispatch, which `on_interrupt` drives every reload.
+    }
+
+    fn clear_compare_flag(&mut self) {
+        // No hardware compare flag to clear.
+    }
+
+    fn on_interrupt(&mut self) {
+        // RTIC dispatches this on every SysTick reload; drive the same wrap
+        // bookkeeping a manually-written `#[exception] fn SysTick()` would,
+        // since RTIC owns the interrupt and nothing else calls it here.
+        self.timer.systick_handler();
+    }
+
+    fn enable_timer(&mut self) {
+        #[cfg(feature = "cortex-m")]
+        // SAFETY: only toggles SYST_CSR.TICKINT, which is safe to do from
+        // any context; it does not touch the reload or current value.
+        unsafe {
+            let csr = &(*cortex_m::peripheral::SYST::PTR).csr;
+            csr.write(csr.read() | 0b010);
+        }
+    }
+
+    fn disable_timer(&mut self) {
+        #[cfg(feature = "cortex-m")]
+        // SAFETY: see `enable_timer`.
+        unsafe {
+            let csr = &(*cortex_m::peripheral::SYST::PTR).csr;
+            csr.write(csr.read() & !0b010);
+        }
+    }
+}
@@ -0,0 +1,83 @@
+// SPDX-License-Identifier: Apache-2.0
+//! A tiny seqlock for the scaling state `Timer::reconfigure` mutates.
+//!
+//! `thumbv6m` (Cortex-M0/M0+, e.g. the SAMD21 the `feather_m0` example
+//! targets) has no 64-bit atomics and no compare-and-swap, so the scaling
+//! factor (`multiplier`/`shift`) and its continuity anchor (`base_ticks`/
+//! `base_cycles`) can't live in `AtomicU64`/`AtomicU32` if `now()` is to keep
+//! working there. Instead they're stored as plain fields behind a sequence
+//! counter, following the same trick the wrap counter itself already uses
+//! by splitting a 64-bit value across two 32-bit words: a writer bumps the
+//! sequence to odd, writes the fields, then bumps it back to even; a reader
+//! retries whenever it observes an odd sequence or the sequence changing
+//! mid-read.
+
+use core::cell::UnsafeCell;
+use core::sync::atomic::{AtomicU32, Ordering};
+
+#[derive(Clone, Copy)]
+pub(crate) struct ScalingState {
+    pub(crate) multiplier: u64,
+    pub(crate) shift: u32,
+    pub(crate) base_ticks: u64,
+    pub(crate) base_cycles: u64,
+}
+
+pub(crate) struct SeqLock {
+    seq: AtomicU32,
+    state: UnsafeCell<ScalingState>,
+}
+
+// SAFETY: `state` is only ever written by a single writer at a time (callers
+// serialize writes with a critical section), and readers only ever read a
+// fully-written snapshot, verified by the sequence counter around it.
+unsafe impl Sync for SeqLock {}
+
+impl SeqLock {
+    pub(crate) const fn new(state: ScalingState) -> Self {
+        Self {
+            seq: AtomicU32::new(0),
+            state: UnsafeCell::new(state),
+        }
+    }
+
+    /// Reads a coherent snapshot, retrying if a write was in progress or ran
+    /// concurrently.
+    ///
+    /// Every load/store here uses `SeqCst`, matching the wrap counters'
+    /// convention elsewhere in the crate (see `Timer::now_cycles`), rather
+    /// than the weaker `Acquire`/`Release` this odd/even handshake would
+    /// otherwise need only one side of — consistency with the rest of the
+    /// crate's race-safe state matters more than the few cycles saved, and
+    /// it's what the crate's loom/Miri coverage is modeled against.
+    pub(crate) fn read(&self) -> ScalingState {
+        loop {
+            let seq_before = self.seq.load(Ordering::SeqCst);
+            if seq_before & 1 != 0 {
+                // A write is in progress.
+                continue;
+            }
+            // SAFETY: no writer can be storing into `state` right now,
+            // because we just observed an even sequence number; we verify
+            // below that no write started (and possibly finished) while we
+            // were reading.
+            let state = unsafe { core::ptr::read(self.state.get()) };
+            let seq_after = self.seq.load(Ordering::SeqCst);
+            if seq_before == seq_after {
+                return state;
+            }
+        }
+    }
+
+    /// Overwrites the state. The caller must ensure no other writer can run
+    /// concurrently (e.g. by holding a critical section).
+    pub(crate) fn write(&self, new_state: ScalingState) {
+        let seq = self.seq.load(Ordering::SeqCst);
+        self.seq.store(seq.wrapping_add(1), Ordering::SeqCst);
+        // SAFETY: the sequence is odd, so any concurrent reader retries
+        // instead of observing a partial write; writers are serialized by
+        // the caller.
+        unsafe { core::ptr::write(self.state.get(), new_state) };
+        self.seq.store(seq.wrapping_add(2), Ordering::SeqCst);
+    }
+}
@@ -0,0 +1,107 @@
+// SPDX-License-Identifier: Apache-2.0
+//! Abstraction over the down-counter [`Timer`](super::Timer) reads.
+//!
+//! `Timer` only needs three things from its counter: the current count, a
+//! read-and-clear wrap flag, and whether a wrap interrupt is pending.
+//! Splitting that out as [`SysTickSource`] means the scaling and
+//! monotonicity logic in `timer.rs` no longer needs `cfg(test)` vs
+//! `cfg(feature = "cortex-m")` branches scattered through it: production
+//! code plugs in [`CortexMSysTick`], while tests (and anyone wanting to
+//! drive the timing math without real hardware) plug in [`MockSysTick`].
+
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+/// A down-counter `Timer` can read to derive monotonic time.
+///
+/// Mirrors the three SysTick registers `Timer` actually touches: the
+/// current count (`SYST_CVR`), the wrap flag (`SYST_CSR.COUNTFLAG`, which
+/// clears on read), and whether the wrap interrupt is pending
+/// (`SCB.ICSR.PENDSTSET`).
+pub trait SysTickSource {
+    /// Returns the counter's current value.
+    fn current(&self) -> u32;
+    /// Returns whether the counter has wrapped since the last call,
+    /// clearing the flag as a side effect (mirrors `COUNTFLAG`).
+    fn read_countflag(&self) -> bool;
+    /// Returns whether the wrap interrupt is pending but hasn't run yet.
+    fn is_pending(&self) -> bool;
+}
+
+/// Reads the real Cortex-M SysTick peripheral.
+#[cfg(feature = "cortex-m")]
+#[derive(Clone, Copy, Default)]
+pub struct CortexMSysTick;
+
+#[cfg(feature = "cortex-m")]
+impl SysTickSource for CortexMSysTick {
+    fn current(&self) -> u32 {
+        cortex_m::peripheral::SYST::get_current()
+    }
+
+    fn read_countflag(&self) -> bool {
+        // SAFETY: only reads SYST_CSR; the hardware clears COUNTFLAG as a
+        // documented side effect of the read, no `&mut SYST` required.
+        unsafe {
+            const COUNTFLAG: u32 = 1 << 16;
+            let csr = (*cortex_m::peripheral::SYST::PTR).csr.read();
+            (csr & COUNTFLAG) != 0
+        }
+    }
+
+    fn is_pending(&self) -> bool {
+        cortex_m::peripheral::SCB::is_pendst_pending()
+    }
+}
+
+/// An in-memory [`SysTickSource`] for tests and host-side driving of the
+/// scaling/monotonicity logic without real hardware.
+///
+/// Lives outside `cfg(test)` so downstream crates can unit-test their own
+/// `Timer<MockSysTick>` usage, run `now()` under loom-style races, or
+/// exercise the scaling math on targets with no SysTick at all.
+#[derive(Default)]
+pub struct MockSysTick {
+    current: AtomicU32,
+    has_wrapped: AtomicBool,
+    pending: AtomicBool,
+}
+
+impl MockSysTick {
+    /// Creates a mock counter starting at `initial`.
+    pub const fn new(initial: u32) -> Self {
+        Self {
+            current: AtomicU32::new(initial),
+            has_wrapped: AtomicBool::new(false),
+            pending: AtomicBool::new(false),
+        }
+    }
+
+    /// Sets the emulated counter value.
+    pub fn set_current(&self, value: u32) {
+        self.current.store(value, Ordering::SeqCst);
+    }
+
+    /// Sets the emulated `COUNTFLAG` bit.
+    pub fn set_countflag(&self, value: bool) {
+        self.has_wrapped.store(value, Ordering::SeqCst);
+    }
+
+    /// Sets the emulated `PENDSTSET` bit.
+    pub fn set_pending(&self, value: bool) {
+        self.pending.store(value, Ordering::SeqCst);
+    }
+}
+
+impl SysTickSource for MockSysTick {
+    fn current(&self) -> u32 {
+        self.current.load(Ordering::SeqCst)
+    }
+
+    fn read_countflag(&self) -> bool {
+        self.has_wrapped.swap(false, Ordering::SeqCst)
+    }
+
+    fn is_pending(&self) -> bool {
+        self.pending.load(Ordering::SeqCst)
+    }
+}
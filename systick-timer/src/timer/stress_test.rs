@@ -0,0 +1,236 @@
+use super::*;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+type Timer = super::Timer<MockSysTick>;
+
+// Use a small reload value to ensure wraps happen very frequently,
+// maximizing the chances of hitting a race condition.
+const RELOAD: u32 = 1000;
+const TEST_DURATION_MS: u64 = 2000; // 2 seconds
+
+#[test]
+fn monotonicity_stress_test() {
+    // The timer is the central piece of state, shared across all threads.
+    // A Mutex is used here to simulate a critical section, preventing the
+    // "ISR" from preempting the "Application" during a `now()` call.
+    let timer = Arc::new(Mutex::new(Timer::new(
+        1_000_000,
+        RELOAD,
+        48_000_000,
+        MockSysTick::new(0),
+    )));
+    timer.lock().unwrap().source().set_current(RELOAD);
+
+    // A shared flag to signal all threads to stop.
+    let stop_signal = Arc::new(AtomicBool::new(false));
+
+    // --- Thread 1: The "Hardware Clock" Simulator ---
+    // This thread's job is to make the clock tick down.
+    let timer_hw = timer.clone();
+    let stop_hw = stop_signal.clone();
+    let hw_thread = thread::spawn(move || {
+        while !stop_hw.load(Ordering::Relaxed) {
+            // Lock the timer to modify its state
+            let timer_guard = timer_hw.lock().unwrap();
+            let current_val = timer_guard.get_syst();
+            if current_val > 0 {
+                timer_guard.source().set_current(current_val - 1);
+            } else {
+                // We've hit 0, time to wrap.
+                timer_guard.source().set_current(RELOAD);
+                // Signal that the ISR is pending. The ISR thread will pick this up.
+                timer_guard.source().set_pending(true);
+            }
+            // Drop the lock by letting timer_guard go out of scope
+            drop(timer_guard);
+            // Sleep for a tiny duration to simulate the clock speed.
+            thread::sleep(Duration::from_nanos(100));
+        }
+    });
+
+    // --- Thread 2: The "ISR" (Interrupt Service Routine) Simulator ---
+    let timer_isr = timer.clone();
+    let stop_isr = stop_signal.clone();
+    let isr_thread = thread::spawn(move || {
+        while !stop_isr.load(Ordering::Relaxed) {
+            // Lock the timer to check and handle the pending interrupt atomically.
+            let timer_guard = timer_isr.lock().unwrap();
+            if timer_guard.is_systick_pending() {
+                timer_guard.source().set_pending(false); // ISR clears the pending bit
+                timer_guard.systick_handler();
+            }
+            drop(timer_guard);
+            // Sleep for a tiny, slightly variable duration to make the timing unpredictable.
+            thread::sleep(Duration::from_micros(1));
+        }
+    });
+
+    // --- Thread 3: The "Application" / Monotonicity Checker ---
+    let timer_app = timer.clone();
+    let stop_app = stop_signal.clone();
+    let app_thread = thread::spawn(move || {
+        let mut last_seen_time = 0;
+        let mut iterations = 0;
+        while !stop_app.load(Ordering::Relaxed) {
+            // Lock the timer to call now(), ensuring the ISR can't run in the middle.
+            let current_time = timer_app.lock().unwrap().now();
+            assert!(
+                current_time >= last_seen_time,
+                "Monotonicity failed! current: {}, last: {}",
+                current_time,
+                last_seen_time
+            );
+            last_seen_time = current_time;
+            iterations += 1;
+        }
+        println!("Checker thread completed {} iterations.", iterations);
+    });
+
+    // Let the threads run for the specified duration.
+    println!("Running stress test for {}ms...", TEST_DURATION_MS);
+    thread::sleep(Duration::from_millis(TEST_DURATION_MS));
+
+    // Signal all threads to stop and wait for them to finish.
+    stop_signal.store(true, Ordering::Relaxed);
+    hw_thread.join().unwrap();
+    isr_thread.join().unwrap();
+    app_thread.join().unwrap();
+
+    println!("Stress test passed.");
+}
+
+/// Exercises `now()` against `systick_handler()` on real threads, with no
+/// `Mutex` serializing them, so Miri's data-race and weak-memory checks (run
+/// with `-Zmiri-compare-exchange-weak-failure-rate`/address-reuse knobs to
+/// taste) can catch an `Acquire`/`Release` mismatch between the ISR's
+/// publication of the wrap counters and `now()`'s consumption of them. Miri
+/// is slow, so iteration counts and the reload value are kept tiny.
+#[cfg(miri)]
+#[test]
+fn miri_weak_memory_wrap_race() {
+    use std::sync::Arc;
+    use std::thread;
+
+    const ITERATIONS: usize = 50;
+    const RELOAD: u32 = 4;
+
+    let timer = Arc::new(Timer::new(1_000, RELOAD, 1_000, MockSysTick::new(0)));
+    timer.source().set_current(RELOAD);
+
+    let timer_hw = timer.clone();
+    let hw_isr_thread = thread::spawn(move || {
+        for _ in 0..ITERATIONS {
+            let current = timer_hw.get_syst();
+            if current > 0 {
+                timer_hw.source().set_current(current - 1);
+            } else {
+                timer_hw.source().set_current(RELOAD);
+                timer_hw.systick_handler();
+            }
+        }
+    });
+
+    let mut last_seen_time = 0;
+    for _ in 0..ITERATIONS {
+        let current_time = timer.now();
+        assert!(
+            current_time >= last_seen_time,
+            "Monotonicity failed under Miri! current: {}, last: {}",
+            current_time,
+            last_seen_time
+        );
+        last_seen_time = current_time;
+    }
+
+    hw_isr_thread.join().unwrap();
+}
+
+/// Loom model of the wrap-counter race between `systick_handler()` and
+/// `now()`.
+///
+/// `Timer` itself can't be dropped straight into `loom::model` because its
+/// atomics are `core::sync::atomic` (needed for a `const fn new()` usable in
+/// `static` initializers), as are those of `MockSysTick`. Instead
+/// this mirrors just the two-atomic wrap accumulator — `inner_wraps` plus
+/// `outer_wraps`, combined into a 64-bit wrap count exactly as `Timer::now`
+/// does — and has loom explore every legal interleaving of a writer
+/// (simulating `systick_handler`) against a reader (simulating `now`) under
+/// the C11 memory model. Loom's state space is exponential in the number of
+/// atomic operations, so the number of simulated wraps is kept tiny.
+#[cfg(loom)]
+mod loom_monotonicity {
+    use loom::sync::atomic::{AtomicU32, Ordering};
+    use loom::thread;
+    use std::sync::Arc;
+
+    struct WrapCounter {
+        inner_wraps: AtomicU32,
+        outer_wraps: AtomicU32,
+    }
+
+    impl WrapCounter {
+        fn new() -> Self {
+            Self {
+                inner_wraps: AtomicU32::new(0),
+                outer_wraps: AtomicU32::new(0),
+            }
+        }
+
+        /// Mirrors `Timer::systick_handler`'s wrap-counter bump.
+        fn bump(&self) {
+            let inner = self.inner_wraps.load(Ordering::Relaxed);
+            self.inner_wraps
+                .store(inner.wrapping_add(1), Ordering::SeqCst);
+            if inner == u32::MAX {
+                let outer = self.outer_wraps.load(Ordering::Relaxed).wrapping_add(1);
+                self.outer_wraps.store(outer, Ordering::SeqCst);
+            }
+        }
+
+        /// Mirrors `Timer::now`'s combined 64-bit wrap read.
+        fn wraps(&self) -> u64 {
+            let inner = self.inner_wraps.load(Ordering::SeqCst) as u64;
+            let outer = self.outer_wraps.load(Ordering::SeqCst) as u64;
+            (outer << 32) | inner
+        }
+    }
+
+    // Small enough that loom's exhaustive search finishes in reasonable time.
+    const WRAPS: u32 = 3;
+
+    #[test]
+    fn wrap_counter_never_goes_backwards() {
+        loom::model(|| {
+            let counter = Arc::new(WrapCounter::new());
+
+            let writer = {
+                let counter = counter.clone();
+                thread::spawn(move || {
+                    for _ in 0..WRAPS {
+                        counter.bump();
+                    }
+                })
+            };
+
+            let mut last = 0u64;
+            for _ in 0..WRAPS {
+                let current = counter.wraps();
+                assert!(
+                    current >= last,
+                    "wrap count went backwards: {} then {}",
+                    last,
+                    current
+                );
+                last = current;
+            }
+
+            writer.join().unwrap();
+
+            let final_wraps = counter.wraps();
+            assert!(final_wraps >= last);
+        });
+    }
+}
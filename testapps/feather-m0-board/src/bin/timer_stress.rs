@@ -11,7 +11,7 @@ use hal::timer::TimerCounter;
 use hal::timer_traits::InterruptDrivenTimer;
 use lib::hal::{self};
 use rtt_target::{rprintln, rtt_init_log};
-use systick_timer::Timer;
+use systick_timer::{CortexMSysTick, Timer};
 use timer_stress::{
     TICK_RESOLUTION, TIMER1_COUNTER, TIMER2_COUNTER, TimerId, check_timer_monotonic,
     configure_interrupts, report_configuration, timer_stress_test,
@@ -27,9 +27,15 @@ const TIMER_ABOVE_HZ: u32 = 15_001;
 
 // Global SysTick timer - accessible from ISRs and main code
 #[cfg(feature = "reload-small")]
-static TIMER: Timer = Timer::new(TICK_RESOLUTION, 0x1FFF, CORE_FREQUENCY as u64);
+static TIMER: Timer<CortexMSysTick> =
+    Timer::new(TICK_RESOLUTION, 0x1FFF, CORE_FREQUENCY as u64, CortexMSysTick);
 #[cfg(not(feature = "reload-small"))]
-static TIMER: Timer = Timer::new(TICK_RESOLUTION, 0xFFFFFF, CORE_FREQUENCY as u64);
+static TIMER: Timer<CortexMSysTick> = Timer::new(
+    TICK_RESOLUTION,
+    0xFFFFFF,
+    CORE_FREQUENCY as u64,
+    CortexMSysTick,
+);
 
 /// Encode priority level for ARM Cortex-M NVIC
 /// ARM Cortex-M0+ uses only 2 bits for priority (4 levels: 0, 1, 2, 3)
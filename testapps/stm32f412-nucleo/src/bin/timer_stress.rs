@@ -7,7 +7,7 @@ use core::sync::atomic::Ordering;
 use hal::rcc::Config;
 use lib::hal::{self, interrupt, prelude::*, timer::Timer as HalTimer};
 use rtt_target::{rprintln, rtt_init_log};
-use systick_timer::Timer;
+use systick_timer::{CortexMSysTick, Timer};
 use timer_stress::{
     TICK_RESOLUTION, TIMER1_COUNTER, TIMER2_COUNTER, TimerId, check_timer_monotonic,
     configure_interrupts, report_configuration, timer_stress_test,
@@ -23,9 +23,15 @@ const TIMER_ABOVE_HZ: u32 = 50_001;
 
 // Global SysTick timer - accessible from ISRs and main code
 #[cfg(feature = "reload-small")]
-static TIMER: Timer = Timer::new(TICK_RESOLUTION, 0x3FF, CORE_FREQUENCY as u64);
+static TIMER: Timer<CortexMSysTick> =
+    Timer::new(TICK_RESOLUTION, 0x3FF, CORE_FREQUENCY as u64, CortexMSysTick);
 #[cfg(not(feature = "reload-small"))]
-static TIMER: Timer = Timer::new(TICK_RESOLUTION, 0xFFFFFF, CORE_FREQUENCY as u64);
+static TIMER: Timer<CortexMSysTick> = Timer::new(
+    TICK_RESOLUTION,
+    0xFFFFFF,
+    CORE_FREQUENCY as u64,
+    CortexMSysTick,
+);
 
 /// Encode priority level for ARM Cortex-M NVIC
 /// ARM Cortex-M uses only the upper 4 bits for priority (on STM32F4)
@@ -2,7 +2,7 @@
 
 use core::sync::atomic::{AtomicU32, Ordering};
 use rtt_target::rprintln;
-use systick_timer::Timer;
+use systick_timer::{CortexMSysTick, Timer};
 
 /// Standard tick resolution for all timer stress tests (1 GHz, 1 ns resolution)
 pub const TICK_RESOLUTION: u64 = 1_000_000_000;
@@ -35,7 +35,7 @@ impl From<TimerId> for &'static str {
 ///
 /// This function takes a timer reference to avoid global static dependency
 pub fn check_timer_monotonic<T: Into<TimerId>>(
-    timer: &Timer,
+    timer: &Timer<CortexMSysTick>,
     timer_id: T,
     last_now: &mut u64,
     core_frequency: u32,
@@ -215,7 +215,7 @@ pub const fn get_test_duration_seconds(full_duration: u64) -> u64 {
 }
 
 /// Run the main timer stress test loop
-pub fn timer_stress_test(timer: &Timer, full_test_duration_secs: u64) {
+pub fn timer_stress_test(timer: &Timer<CortexMSysTick>, full_test_duration_secs: u64) {
     let start_time = timer.now();
     let mut last_log_time = start_time;
     let mut iteration_count = 0u64;